@@ -0,0 +1,164 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use glam::IVec2;
+use sark_grids::{Grid, GridPoint, Size2d};
+
+use crate::pathmap::PathMap;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct HeapEntry {
+    cost: i32,
+    pos: IVec2,
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap` (a max-heap) pops the lowest cost first.
+        other.cost.cmp(&self.cost)
+    }
+}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A Dijkstra distance field computed over a [PathMap], as popularized by
+/// roguelike "Dijkstra maps" and the influence maps used in games like
+/// MegaGlest.
+///
+/// Every reachable cell stores the shortest-path cost to the nearest of one
+/// or more goal cells. Agents can chase the goal by descending the gradient
+/// with [DijkstraMap::downhill], or flee it by doing the same over a
+/// [DijkstraMap::negate]d copy of the map.
+pub struct DijkstraMap {
+    values: Grid<Option<i32>>,
+}
+
+impl DijkstraMap {
+    /// Build a distance map by relaxing outward from `goals` over `map`.
+    ///
+    /// `size` must match the size of `map`. Cells farther than
+    /// `max_distance` (if given) are left unreached.
+    pub fn build(
+        map: &impl PathMap,
+        size: impl Size2d,
+        goals: impl IntoIterator<Item = IVec2>,
+        max_distance: Option<i32>,
+    ) -> Self {
+        let mut values: Grid<Option<i32>> = Grid::default(size);
+        let mut open = BinaryHeap::new();
+
+        for goal in goals {
+            values[goal] = Some(0);
+            open.push(HeapEntry { cost: 0, pos: goal });
+        }
+
+        while let Some(HeapEntry { cost, pos }) = open.pop() {
+            if values[pos].is_some_and(|best| cost > best) {
+                continue;
+            }
+
+            for adj in map.exits(pos) {
+                let adj_cost = cost + map.cost(pos, adj);
+                if let Some(max) = max_distance {
+                    if adj_cost > max {
+                        continue;
+                    }
+                }
+                if values[adj].is_none_or(|best| adj_cost < best) {
+                    values[adj] = Some(adj_cost);
+                    open.push(HeapEntry { cost: adj_cost, pos: adj });
+                }
+            }
+        }
+
+        Self { values }
+    }
+
+    /// The shortest-path distance to the nearest goal, or `None` if `p` is
+    /// unreached (blocked, out of bounds, or past the `max_distance` cutoff).
+    pub fn distance(&self, p: impl GridPoint) -> Option<i32> {
+        let p = p.xy();
+        if !self.values.in_bounds(p) {
+            return None;
+        }
+        self.values[p]
+    }
+
+    /// The neighbor of `p` with the lowest distance value: the next step
+    /// along the shortest path toward the nearest goal.
+    pub fn downhill(&self, map: &impl PathMap, p: impl GridPoint) -> Option<IVec2> {
+        map.exits(p)
+            .filter_map(|adj| self.values[adj].map(|d| (adj, d)))
+            .min_by_key(|&(_, d)| d)
+            .map(|(adj, _)| adj)
+    }
+
+    /// Produce a "flee" field: every reached cell's distance is negated, so
+    /// descending its gradient moves away from the goals instead of toward
+    /// them.
+    pub fn negate(&self) -> Self {
+        self.scaled(-1)
+    }
+
+    /// Scale every reached cell's distance by `factor`.
+    pub fn scaled(&self, factor: i32) -> Self {
+        let mut values = self.values.clone();
+        for v in values.slice_mut() {
+            *v = v.map(|d| d * factor);
+        }
+        Self { values }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathmap::PathMap2d;
+
+    #[test]
+    fn distance_grows_with_chebyshev_distance_from_goal() {
+        let map = PathMap2d::new([5, 5]);
+        let dmap = DijkstraMap::build(&map, [5, 5], [IVec2::new(2, 2)], None);
+
+        // The default Diagonal connectivity means distance is Chebyshev, not
+        // taxicab: reaching (0, 0) takes 2 diagonal steps, not 4.
+        assert_eq!(dmap.distance(IVec2::new(2, 2)), Some(0));
+        assert_eq!(dmap.distance(IVec2::new(3, 2)), Some(1));
+        assert_eq!(dmap.distance(IVec2::new(0, 0)), Some(2));
+    }
+
+    #[test]
+    fn downhill_steps_toward_the_goal() {
+        let map = PathMap2d::new([5, 5]);
+        let goal = IVec2::new(4, 4);
+        let dmap = DijkstraMap::build(&map, [5, 5], [goal], None);
+
+        let next = dmap.downhill(&map, IVec2::new(0, 0)).unwrap();
+
+        assert!(dmap.distance(next) < dmap.distance(IVec2::new(0, 0)));
+    }
+
+    #[test]
+    fn negate_reverses_the_gradient() {
+        let map = PathMap2d::new([5, 5]);
+        let goal = IVec2::new(2, 2);
+        let dmap = DijkstraMap::build(&map, [5, 5], [goal], None);
+        let flee = dmap.negate();
+
+        assert_eq!(flee.distance(goal), Some(0));
+        assert_eq!(flee.distance(IVec2::new(0, 0)), Some(-2));
+    }
+
+    #[test]
+    fn max_distance_cuts_off_far_cells() {
+        let map = PathMap2d::new([5, 5]);
+        let dmap = DijkstraMap::build(&map, [5, 5], [IVec2::new(0, 0)], Some(1));
+
+        assert_eq!(dmap.distance(IVec2::new(1, 0)), Some(1));
+        assert_eq!(dmap.distance(IVec2::new(2, 0)), None);
+    }
+}
@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+
+use glam::IVec2;
+
+use crate::pathfinder::Pathfinder;
+use crate::pathmap::{PathMap, PathMap2d};
+
+/// Identifies an abstract node in a [PathCache]'s hierarchical graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct NodeId(usize);
+
+#[derive(Debug)]
+struct AbstractNode {
+    pos: IVec2,
+    chunk: IVec2,
+}
+
+/// A precomputed edge between two abstract nodes, either a cheap cross-border
+/// hop between adjacent chunks or an intra-chunk path refined with A*.
+#[derive(Clone, Debug)]
+struct AbstractEdge {
+    cost: i32,
+    /// The concrete cells of the edge, in order from its start node to its
+    /// end node. Empty for the single-step cross-border hop between two
+    /// entrance nodes, since those are adjacent cells.
+    path: Vec<IVec2>,
+}
+
+/// An HPA*-style hierarchical path cache for large [PathMap2d] grids.
+///
+/// The grid is partitioned into fixed-size chunks. Contiguous unblocked
+/// segments along each chunk border are detected as "entrances", and an
+/// abstract node is placed at every entrance's midpoint. Intra-chunk edges
+/// between nodes of the same chunk are precomputed by running A* restricted
+/// to that chunk's cells, and cheap cross-border edges connect nodes of
+/// adjacent chunks. A query A*s over this small abstract graph and refines
+/// each abstract edge back into concrete cells on demand, so repeated
+/// long-distance queries cost O(chunks) instead of O(map).
+///
+/// Call [PathCache::invalidate] after mutating the underlying map so only
+/// the affected chunk (and its immediate neighbors) are recomputed.
+pub struct PathCache {
+    chunk_size: i32,
+    map_size: IVec2,
+    next_id: usize,
+    nodes: HashMap<NodeId, AbstractNode>,
+    node_ids: HashMap<IVec2, NodeId>,
+    edges: HashMap<(NodeId, NodeId), AbstractEdge>,
+    chunk_nodes: HashMap<IVec2, Vec<NodeId>>,
+    /// Entrance node ids produced by `add_border_entrances(chunk, dir)`,
+    /// keyed the same way, so a border can be torn down and rescanned
+    /// without touching unrelated chunks.
+    border_nodes: HashMap<(IVec2, IVec2), Vec<NodeId>>,
+}
+
+impl PathCache {
+    /// Build a new hierarchical path cache for `map`, partitioned into
+    /// `chunk_size` x `chunk_size` chunks.
+    pub fn new(map: &PathMap2d, chunk_size: i32) -> Self {
+        let map_size = IVec2::new(map.width() as i32, map.height() as i32);
+        let mut cache = Self {
+            chunk_size,
+            map_size,
+            next_id: 0,
+            nodes: HashMap::new(),
+            node_ids: HashMap::new(),
+            edges: HashMap::new(),
+            chunk_nodes: HashMap::new(),
+            border_nodes: HashMap::new(),
+        };
+
+        let counts = cache.chunk_counts();
+        for cy in 0..counts.y {
+            for cx in 0..counts.x {
+                let chunk = IVec2::new(cx, cy);
+                if cx + 1 < counts.x {
+                    cache.add_border_entrances(map, chunk, IVec2::new(1, 0));
+                }
+                if cy + 1 < counts.y {
+                    cache.add_border_entrances(map, chunk, IVec2::new(0, 1));
+                }
+            }
+        }
+
+        let chunks: Vec<IVec2> = cache.chunk_nodes.keys().copied().collect();
+        for chunk in chunks {
+            cache.rebuild_chunk_edges(map, chunk);
+        }
+        cache
+    }
+
+    fn chunk_of(&self, p: IVec2) -> IVec2 {
+        IVec2::new(p.x.div_euclid(self.chunk_size), p.y.div_euclid(self.chunk_size))
+    }
+
+    fn chunk_counts(&self) -> IVec2 {
+        IVec2::new(
+            (self.map_size.x + self.chunk_size - 1) / self.chunk_size,
+            (self.map_size.y + self.chunk_size - 1) / self.chunk_size,
+        )
+    }
+
+    /// The inclusive-min/exclusive-max cell bounds of `chunk`.
+    fn chunk_rect(&self, chunk: IVec2) -> (IVec2, IVec2) {
+        let min = chunk * self.chunk_size;
+        let max = IVec2::new(
+            (min.x + self.chunk_size).min(self.map_size.x),
+            (min.y + self.chunk_size).min(self.map_size.y),
+        );
+        (min, max)
+    }
+
+    fn node_at(&mut self, pos: IVec2, chunk: IVec2) -> NodeId {
+        if let Some(&id) = self.node_ids.get(&pos) {
+            return id;
+        }
+        let id = NodeId(self.next_id);
+        self.next_id += 1;
+        self.nodes.insert(id, AbstractNode { pos, chunk });
+        self.node_ids.insert(pos, id);
+        self.chunk_nodes.entry(chunk).or_default().push(id);
+        id
+    }
+
+    fn remove_node(&mut self, id: NodeId) {
+        if let Some(node) = self.nodes.remove(&id) {
+            self.node_ids.remove(&node.pos);
+            if let Some(ids) = self.chunk_nodes.get_mut(&node.chunk) {
+                ids.retain(|&n| n != id);
+            }
+            self.edges.retain(|&(a, b), _| a != id && b != id);
+        }
+    }
+
+    /// Tear down the entrance nodes (and their cross-border edge) previously
+    /// computed for the border between `chunk` and its neighbor in `dir`.
+    fn remove_border_entrances(&mut self, chunk: IVec2, dir: IVec2) {
+        if let Some(ids) = self.border_nodes.remove(&(chunk, dir)) {
+            // Ids are stored as (this side, neighbor side) pairs.
+            for pair in ids.chunks(2) {
+                self.edges.remove(&(pair[0], pair[1]));
+                self.edges.remove(&(pair[1], pair[0]));
+            }
+            // A cell on a chunk corner can be an entrance of two borders at
+            // once; keep its node while the other border still uses it.
+            for id in ids {
+                if !self.border_nodes.values().any(|other| other.contains(&id)) {
+                    self.remove_node(id);
+                }
+            }
+        }
+    }
+
+    /// Scan the border `chunk` shares with its neighbor in `dir` (`(1, 0)`
+    /// for the right border, `(0, 1)` for the bottom border), and place one
+    /// node pair at the midpoint of every contiguous unblocked segment.
+    fn add_border_entrances(&mut self, map: &PathMap2d, chunk: IVec2, dir: IVec2) {
+        let origin = chunk * self.chunk_size;
+        let neighbor_chunk = chunk + dir;
+        let len = if dir.x != 0 {
+            self.chunk_size.min(self.map_size.y - origin.y)
+        } else {
+            self.chunk_size.min(self.map_size.x - origin.x)
+        };
+
+        let border_cells = |i: i32| -> (IVec2, IVec2) {
+            if dir.x != 0 {
+                (
+                    IVec2::new(origin.x + self.chunk_size - 1, origin.y + i),
+                    IVec2::new(origin.x + self.chunk_size, origin.y + i),
+                )
+            } else {
+                (
+                    IVec2::new(origin.x + i, origin.y + self.chunk_size - 1),
+                    IVec2::new(origin.x + i, origin.y + self.chunk_size),
+                )
+            }
+        };
+
+        let passable = |i: i32| {
+            let (a, b) = border_cells(i);
+            map.in_bounds(a) && map.in_bounds(b) && !map.is_obstacle(a) && !map.is_obstacle(b)
+        };
+
+        let mut border_ids = Vec::new();
+        let mut run_start = None;
+        for i in 0..=len {
+            match (i < len && passable(i), run_start) {
+                (true, None) => run_start = Some(i),
+                (false, Some(start)) => {
+                    let mid = (start + i - 1) / 2;
+                    let (a, b) = border_cells(mid);
+                    let node_a = self.node_at(a, chunk);
+                    let node_b = self.node_at(b, neighbor_chunk);
+                    self.set_edge(node_a, node_b, 1, Vec::new());
+                    border_ids.push(node_a);
+                    border_ids.push(node_b);
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+        self.border_nodes.insert((chunk, dir), border_ids);
+    }
+
+    fn set_edge(&mut self, a: NodeId, b: NodeId, cost: i32, path: Vec<IVec2>) {
+        self.edges.insert((a, b), AbstractEdge { cost, path: path.clone() });
+        self.edges.insert(
+            (b, a),
+            AbstractEdge {
+                cost,
+                path: path.into_iter().rev().collect(),
+            },
+        );
+    }
+
+    /// Recompute the intra-chunk edges between every pair of nodes on
+    /// `chunk`'s borders, using A* restricted to `chunk`'s own cells so an
+    /// edge can never silently route through a different chunk.
+    fn rebuild_chunk_edges(&mut self, map: &PathMap2d, chunk: IVec2) {
+        let Some(node_ids) = self.chunk_nodes.get(&chunk).cloned() else {
+            return;
+        };
+        let (min, max) = self.chunk_rect(chunk);
+
+        for (i, &a) in node_ids.iter().enumerate() {
+            for &b in &node_ids[i + 1..] {
+                self.edges.remove(&(a, b));
+                self.edges.remove(&(b, a));
+
+                let start = self.nodes[&a].pos;
+                let end = self.nodes[&b].pos;
+                if let Some((path, cost)) = bounded_astar(map, min, max, start, end) {
+                    self.set_edge(a, b, cost, path);
+                }
+            }
+        }
+    }
+
+    /// Recompute whichever chunk contains `cell`, along with its immediate
+    /// neighbors: the mutated chunk's border entrances are rescanned (an
+    /// obstacle can open or close an entrance), and every touched chunk's
+    /// intra-chunk edges are rebuilt.
+    ///
+    /// Call this after mutating the map (e.g. via [PathMap2d::set_obstacle])
+    /// so the cache stays correct without rebuilding the whole hierarchy.
+    pub fn invalidate(&mut self, map: &PathMap2d, cell: IVec2) {
+        let chunk = self.chunk_of(cell);
+
+        // The four borders that touch `chunk`: its own right/bottom borders,
+        // and its left/top neighbors' right/bottom borders.
+        let borders = [
+            (chunk, IVec2::new(1, 0)),
+            (chunk, IVec2::new(0, 1)),
+            (chunk - IVec2::new(1, 0), IVec2::new(1, 0)),
+            (chunk - IVec2::new(0, 1), IVec2::new(0, 1)),
+        ];
+        for (border_chunk, dir) in borders {
+            if self.border_nodes.contains_key(&(border_chunk, dir)) {
+                self.remove_border_entrances(border_chunk, dir);
+                self.add_border_entrances(map, border_chunk, dir);
+            }
+        }
+
+        let mut touched = vec![chunk];
+        for dir in [
+            IVec2::new(1, 0),
+            IVec2::new(-1, 0),
+            IVec2::new(0, 1),
+            IVec2::new(0, -1),
+        ] {
+            let neighbor = chunk + dir;
+            if self.chunk_nodes.contains_key(&neighbor) {
+                touched.push(neighbor);
+            }
+        }
+        for c in touched {
+            self.rebuild_chunk_edges(map, c);
+        }
+    }
+
+    /// Find a path from `start` to `end`, searching the small abstract graph
+    /// and refining each abstract edge back into concrete cells.
+    pub fn find_path(&self, map: &PathMap2d, start: IVec2, end: IVec2) -> Option<Vec<IVec2>> {
+        let mut pf = Pathfinder::new();
+        let start_chunk = self.chunk_of(start);
+        let end_chunk = self.chunk_of(end);
+
+        // Both points fall in the same chunk: a direct concrete search is
+        // already as cheap as refining an abstract edge.
+        if start_chunk == end_chunk {
+            return pf.astar(map, start, end);
+        }
+
+        // Splice `start` and `end` into the abstract graph as temporary
+        // nodes linked to every entrance of their own chunk, then build a
+        // plain adjacency list over abstract-node positions (including the
+        // two temporary nodes) to search with `pathfinding`'s astar.
+        let mut legs: HashMap<(IVec2, IVec2), Vec<IVec2>> = HashMap::new();
+        let mut adjacency: HashMap<IVec2, Vec<(IVec2, i32)>> = HashMap::new();
+
+        for (&(a, b), edge) in &self.edges {
+            let (from, to) = (self.nodes[&a].pos, self.nodes[&b].pos);
+            adjacency.entry(from).or_default().push((to, edge.cost));
+            legs.insert((from, to), edge.path.clone());
+        }
+
+        for (pos, chunk) in [(start, start_chunk), (end, end_chunk)] {
+            let (min, max) = self.chunk_rect(chunk);
+            for &id in self.chunk_nodes.get(&chunk).into_iter().flatten() {
+                let node_pos = self.nodes[&id].pos;
+                let Some((path, cost)) = bounded_astar(map, min, max, pos, node_pos) else {
+                    continue;
+                };
+                adjacency.entry(pos).or_default().push((node_pos, cost));
+                legs.insert((pos, node_pos), path.clone());
+                adjacency.entry(node_pos).or_default().push((pos, cost));
+                legs.insert((node_pos, pos), path.into_iter().rev().collect());
+            }
+        }
+
+        let (abstract_path, _cost) = pathfinding::prelude::astar(
+            &start,
+            |&p| adjacency.get(&p).cloned().unwrap_or_default(),
+            |&p| map.distance(p, end) as i32,
+            |&p| p == end,
+        )?;
+
+        let mut full_path = vec![start];
+        for window in abstract_path.windows(2) {
+            let leg = legs.get(&(window[0], window[1]))?;
+            if leg.is_empty() {
+                full_path.push(window[1]);
+            } else {
+                full_path.extend(leg.iter().skip(1).copied());
+            }
+        }
+        Some(full_path)
+    }
+}
+
+/// Run A* between `start` and `end`, considering only cells in `[min, max)`.
+/// Used to keep intra-chunk edges from silently routing through a
+/// different chunk.
+fn bounded_astar(
+    map: &PathMap2d,
+    min: IVec2,
+    max: IVec2,
+    start: IVec2,
+    end: IVec2,
+) -> Option<(Vec<IVec2>, i32)> {
+    pathfinding::prelude::astar(
+        &start,
+        |&p| {
+            map.exits(p)
+                .filter(|adj| adj.x >= min.x && adj.x < max.x && adj.y >= min.y && adj.y < max.y)
+                .map(|adj| (adj, map.cost(p, adj)))
+                .collect::<Vec<_>>()
+        },
+        |&p| map.distance(p, end) as i32,
+        |&p| p == end,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_map(size: i32) -> PathMap2d {
+        PathMap2d::new([size, size])
+    }
+
+    #[test]
+    fn finds_path_across_chunks() {
+        let map = open_map(12);
+        let cache = PathCache::new(&map, 4);
+
+        let path = cache
+            .find_path(&map, IVec2::new(0, 0), IVec2::new(11, 11))
+            .unwrap();
+
+        assert_eq!(*path.first().unwrap(), IVec2::new(0, 0));
+        assert_eq!(*path.last().unwrap(), IVec2::new(11, 11));
+    }
+
+    #[test]
+    fn invalidate_removes_path_through_new_obstacle() {
+        let mut map = open_map(8);
+        let mut cache = PathCache::new(&map, 4);
+
+        // Block the entire shared border between the two left chunks and
+        // the two right chunks, except one opening at row 0.
+        for y in 1..8 {
+            map.set_obstacle([3, y], true);
+        }
+        for y in 1..8 {
+            cache.invalidate(&map, IVec2::new(3, y));
+        }
+
+        let path = cache
+            .find_path(&map, IVec2::new(0, 0), IVec2::new(7, 7))
+            .unwrap();
+
+        // The only crossing left is at row 0, so the path must pass through it.
+        assert!(path.iter().any(|p| p.x == 3 && p.y == 0));
+        assert!(path.iter().all(|p| !(p.x == 3 && p.y != 0)));
+    }
+}
@@ -0,0 +1,10 @@
+mod dijkstra_map;
+mod hpa;
+mod momentum;
+mod pathfinder;
+mod pathmap;
+
+pub use dijkstra_map::DijkstraMap;
+pub use hpa::PathCache;
+pub use pathfinder::Pathfinder;
+pub use pathmap::{Connectivity, GridCell, PathMap, PathMap2DHeight, PathMap2DWeighted, PathMap2d};
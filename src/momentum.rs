@@ -0,0 +1,136 @@
+use glam::IVec2;
+use pathfinding::prelude::astar;
+use sark_grids::GridPoint;
+
+use crate::pathfinder::Pathfinder;
+use crate::pathmap::PathMap;
+
+const CARDINAL_DIRS: [IVec2; 4] = [
+    IVec2::new(0, -1),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 0),
+    IVec2::new(1, 0),
+];
+
+/// A momentum-constrained search node: position plus the direction and run
+/// length the agent arrived with, since a plain position isn't enough state
+/// to enforce a minimum/maximum run before turning.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct MomentumState {
+    pos: IVec2,
+    dir: Option<IVec2>,
+    run: u32,
+}
+
+impl Pathfinder {
+    /// Find the shortest path from `start` to `end` for an agent that must
+    /// travel at least `min_run` and at most `max_run` cells in a cardinal
+    /// direction before turning, and can never reverse.
+    ///
+    /// Pass `min_run: 0, max_run: u32::MAX` for an unconstrained agent. The
+    /// search node is `(position, incoming direction, run length)` rather
+    /// than just `position`, so the open/closed sets are keyed on the full
+    /// state; the goal is only accepted once `run_length >= min_run`.
+    pub fn astar_momentum(
+        &mut self,
+        map: &impl PathMap,
+        start: impl GridPoint,
+        end: impl GridPoint,
+        min_run: u32,
+        max_run: u32,
+    ) -> Option<Vec<IVec2>> {
+        let start = start.xy();
+        let end = end.xy();
+        let start_state = MomentumState {
+            pos: start,
+            dir: None,
+            run: 0,
+        };
+
+        let (path, _cost) = astar(
+            &start_state,
+            |state| momentum_successors(map, *state, min_run, max_run),
+            |state| map.distance(state.pos, end) as i32,
+            |state| state.pos == end && state.run >= min_run,
+        )?;
+
+        Some(path.into_iter().map(|state| state.pos).collect())
+    }
+}
+
+fn momentum_successors(
+    map: &impl PathMap,
+    state: MomentumState,
+    min_run: u32,
+    max_run: u32,
+) -> Vec<(MomentumState, i32)> {
+    let exits: Vec<IVec2> = map.exits(state.pos).collect();
+
+    CARDINAL_DIRS
+        .into_iter()
+        .filter(|&dir| match state.dir {
+            None => true,
+            Some(incoming) if dir == -incoming => false,
+            Some(incoming) if dir == incoming => state.run < max_run,
+            Some(_) => state.run >= min_run,
+        })
+        .filter_map(|dir| {
+            let next_pos = state.pos + dir;
+            if !exits.contains(&next_pos) {
+                return None;
+            }
+            let cost = map.cost(state.pos, next_pos);
+            let run = if state.dir == Some(dir) { state.run + 1 } else { 1 };
+            Some((
+                MomentumState {
+                    pos: next_pos,
+                    dir: Some(dir),
+                    run,
+                },
+                cost,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathmap::{Connectivity, PathMap2d};
+
+    #[test]
+    fn max_run_forces_a_turn_before_continuing() {
+        let map = PathMap2d::new([10, 10]).with_connectivity(Connectivity::Orthogonal);
+        let mut pf = Pathfinder::new();
+
+        let path = pf
+            .astar_momentum(&map, IVec2::new(0, 0), IVec2::new(5, 0), 0, 2)
+            .unwrap();
+
+        let longest_run = path
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .fold((IVec2::ZERO, 0, 0), |(prev_dir, run, longest), dir| {
+                let run = if dir == prev_dir { run + 1 } else { 1 };
+                (dir, run, longest.max(run))
+            })
+            .2;
+
+        assert!(longest_run <= 2, "a single direction ran for {longest_run} steps, expected at most 2");
+    }
+
+    #[test]
+    fn min_run_forbids_stopping_mid_leg() {
+        let map = PathMap2d::new([10, 10]).with_connectivity(Connectivity::Orthogonal);
+        let mut pf = Pathfinder::new();
+
+        // The goal is only 1 cell away, but a leg must run for at least 2
+        // cells, so the agent can't just take a single step and stop.
+        let path = pf
+            .astar_momentum(&map, IVec2::new(0, 0), IVec2::new(1, 0), 2, 4)
+            .unwrap();
+
+        assert_ne!(path, vec![IVec2::new(0, 0), IVec2::new(1, 0)]);
+        assert_eq!(*path.last().unwrap(), IVec2::new(1, 0));
+    }
+}
@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use glam::IVec2;
+use pathfinding::prelude::astar;
+use sark_grids::GridPoint;
+
+use crate::pathmap::PathMap;
+
+/// Runs pathfinding queries against a [PathMap].
+#[derive(Debug, Default)]
+pub struct Pathfinder;
+
+impl Pathfinder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Find the shortest path between `start` and `end` on `map`, using A*.
+    ///
+    /// Returns `None` if no path exists.
+    pub fn astar(
+        &mut self,
+        map: &impl PathMap,
+        start: impl GridPoint,
+        end: impl GridPoint,
+    ) -> Option<Vec<IVec2>> {
+        let start = start.xy();
+        let end = end.xy();
+
+        let (path, _cost) = astar(
+            &start,
+            |&p| {
+                map.exits(p)
+                    .map(|adj| (adj, map.cost(p, adj)))
+                    .collect::<Vec<_>>()
+            },
+            |&p| map.distance(p, end) as i32,
+            |&p| p == end,
+        )?;
+
+        Some(path)
+    }
+
+    /// Find a near-optimal any-angle path between `start` and `end` using
+    /// Theta*: a variant of A* where a node's parent is replaced by its
+    /// grandparent whenever there's line of sight between them, so the
+    /// resulting path isn't constrained to zig-zag along cell edges.
+    ///
+    /// Returns `None` if no path exists.
+    pub fn astar_theta(
+        &mut self,
+        map: &impl PathMap,
+        start: impl GridPoint,
+        end: impl GridPoint,
+    ) -> Option<Vec<IVec2>> {
+        let start = start.xy();
+        let end = end.xy();
+
+        let mut open = BinaryHeap::new();
+        let mut g_score = HashMap::new();
+        let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+
+        g_score.insert(start, 0);
+        open.push(ThetaEntry {
+            f: map.distance(start, end) as i32,
+            pos: start,
+        });
+
+        while let Some(ThetaEntry { pos, .. }) = open.pop() {
+            if pos == end {
+                return Some(reconstruct_theta_path(&came_from, pos));
+            }
+
+            let g = g_score[&pos];
+            let grandparent = came_from.get(&pos).copied();
+
+            for adj in map.exits(pos) {
+                let (from, from_g, step_cost) = match grandparent
+                    .and_then(|gp| line_of_sight_cost(map, gp, adj).map(|cost| (gp, cost)))
+                {
+                    Some((gp, cost)) => (gp, g_score[&gp], cost),
+                    None => (pos, g, map.cost(pos, adj)),
+                };
+                let tentative_g = from_g + step_cost;
+
+                if tentative_g < *g_score.get(&adj).unwrap_or(&i32::MAX) {
+                    g_score.insert(adj, tentative_g);
+                    came_from.insert(adj, from);
+                    open.push(ThetaEntry {
+                        f: tentative_g + map.distance(adj, end) as i32,
+                        pos: adj,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Smooth a grid-aligned `path` by greedily connecting each waypoint to
+    /// the farthest later waypoint it has line of sight to, dropping the
+    /// waypoints in between. A Theta*-style post-process for paths produced
+    /// by [Pathfinder::astar].
+    pub fn smooth(&self, map: &impl PathMap, path: &[IVec2]) -> Vec<IVec2> {
+        if path.len() < 3 {
+            return path.to_vec();
+        }
+
+        let mut smoothed = vec![path[0]];
+        let mut i = 0;
+        while i < path.len() - 1 {
+            let mut farthest = i + 1;
+            for j in (i + 2..path.len()).rev() {
+                if has_line_of_sight(map, path[i], path[j]) {
+                    farthest = j;
+                    break;
+                }
+            }
+            smoothed.push(path[farthest]);
+            i = farthest;
+        }
+        smoothed
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct ThetaEntry {
+    f: i32,
+    pos: IVec2,
+}
+
+impl Ord for ThetaEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap` (a max-heap) pops the lowest `f` first.
+        other.f.cmp(&self.f)
+    }
+}
+
+impl PartialOrd for ThetaEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn reconstruct_theta_path(came_from: &HashMap<IVec2, IVec2>, mut pos: IVec2) -> Vec<IVec2> {
+    let mut path = vec![pos];
+    while let Some(&prev) = came_from.get(&pos) {
+        path.push(prev);
+        pos = prev;
+    }
+    path.reverse();
+    path
+}
+
+/// Walk the grid cells of a Bresenham line from `a` to `b`, inclusive.
+fn bresenham_line(a: IVec2, b: IVec2) -> Vec<IVec2> {
+    let (mut x0, mut y0) = (a.x, a.y);
+    let (x1, y1) = (b.x, b.y);
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    let mut points = Vec::new();
+    loop {
+        points.push(IVec2::new(x0, y0));
+        if x0 == x1 && y0 == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+    points
+}
+
+/// Whether every step of the Bresenham line from `a` to `b` is a valid move
+/// on `map`, i.e. each cell is a reachable exit of the one before it.
+fn has_line_of_sight(map: &impl PathMap, a: IVec2, b: IVec2) -> bool {
+    line_of_sight_cost(map, a, b).is_some()
+}
+
+/// The total `PathMap::cost` of walking the Bresenham line from `a` to `b`,
+/// one step at a time, or `None` if any step along it isn't a valid move on
+/// `map`. Used instead of a single `map.cost(a, b)` call because `cost` is a
+/// single-step contract: `a` and `b` here are often many cells apart along a
+/// line-of-sight shortcut.
+fn line_of_sight_cost(map: &impl PathMap, a: IVec2, b: IVec2) -> Option<i32> {
+    let line = bresenham_line(a, b);
+    let mut cost = 0;
+    for w in line.windows(2) {
+        if !map.exits(w[0]).any(|adj| adj == w[1]) {
+            return None;
+        }
+        cost += map.cost(w[0], w[1]);
+    }
+    Some(cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathmap::{PathMap2DHeight, PathMap2d};
+    use sark_grids::Grid;
+
+    #[test]
+    fn smooth_collapses_straight_open_path() {
+        let map = PathMap2d::new([5, 5]);
+        let zigzag = vec![
+            IVec2::new(0, 0),
+            IVec2::new(1, 1),
+            IVec2::new(2, 2),
+            IVec2::new(3, 3),
+            IVec2::new(4, 4),
+        ];
+
+        let mut pf = Pathfinder::new();
+        let smoothed = pf.smooth(&map, &zigzag);
+
+        assert_eq!(smoothed, vec![IVec2::new(0, 0), IVec2::new(4, 4)]);
+    }
+
+    #[test]
+    fn astar_theta_reaches_goal_on_open_map() {
+        let map = PathMap2d::new([6, 6]);
+        let mut pf = Pathfinder::new();
+
+        let path = pf
+            .astar_theta(&map, IVec2::new(0, 0), IVec2::new(5, 5))
+            .unwrap();
+
+        assert_eq!(*path.first().unwrap(), IVec2::new(0, 0));
+        assert_eq!(*path.last().unwrap(), IVec2::new(5, 5));
+    }
+
+    #[test]
+    fn line_of_sight_cost_sums_every_step_not_just_endpoints() {
+        let mut heights: Grid<i32> = Grid::default([5, 1]);
+        for x in 0..5 {
+            heights[[x, 0]] = x as i32;
+        }
+        let map = PathMap2DHeight::new(heights).with_slope_penalty(5);
+
+        let cost = line_of_sight_cost(&map, IVec2::new(0, 0), IVec2::new(4, 0)).unwrap();
+
+        // 4 steps, each climbing 1 unit: (1 + 1*5) * 4 = 24. A single
+        // `map.cost` call on the endpoints would wrongly give 1 + 4*5 = 21.
+        assert_eq!(cost, 24);
+    }
+}
@@ -12,6 +12,32 @@ pub trait PathMap {
     fn distance(&self, a: impl GridPoint, b: impl GridPoint) -> usize;
 }
 
+/// Controls which neighboring cells a [PathMap] considers valid movement exits.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Only the 4 orthogonal neighbors are valid exits.
+    Orthogonal,
+    /// All 8 neighbors are valid exits, including diagonals that cut between
+    /// two blocked orthogonal cells.
+    #[default]
+    Diagonal,
+    /// All 8 neighbors are valid exits, but a diagonal move is only allowed
+    /// when both of the orthogonal cells it passes between are unblocked.
+    DiagonalNoCornerCut,
+}
+
+/// Returns `true` if `adj` is not a diagonal move from `origin`, or if both
+/// orthogonal cells shared between `origin` and a diagonal `adj` pass `is_clear`.
+fn corner_cells_clear(origin: IVec2, adj: IVec2, is_clear: impl Fn(IVec2) -> bool) -> bool {
+    let delta = adj - origin;
+    if delta.x == 0 || delta.y == 0 {
+        return true;
+    }
+    let corner_a = origin + IVec2::new(delta.x, 0);
+    let corner_b = origin + IVec2::new(0, delta.y);
+    is_clear(corner_a) && is_clear(corner_b)
+}
+
 /// A pathmap represented as a 2d grid of [bool].
 ///
 /// Note that a grid position is considered an obstacle if it is set to `true`.
@@ -30,6 +56,7 @@ pub trait PathMap {
 /// ```
 pub struct PathMap2d {
     grid: Grid<bool>,
+    connectivity: Connectivity,
 }
 
 impl PathMap2d {
@@ -37,9 +64,16 @@ impl PathMap2d {
     pub fn new(size: impl Size2d) -> Self {
         Self {
             grid: Grid::default(size),
+            connectivity: Connectivity::default(),
         }
     }
 
+    /// Set the [Connectivity] used to determine valid movement exits.
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
     pub fn is_obstacle(&self, p: impl GridPoint) -> bool {
         self[p]
     }
@@ -47,6 +81,11 @@ impl PathMap2d {
     pub fn set_obstacle(&mut self, p: impl GridPoint, v: bool) {
         self[p] = v;
     }
+
+    /// A cell blocks movement if it's out of bounds or marked as an obstacle.
+    fn blocks_movement(&self, p: IVec2) -> bool {
+        !self.in_bounds(p) || self[p]
+    }
 }
 
 impl std::ops::DerefMut for PathMap2d {
@@ -66,15 +105,30 @@ impl std::ops::Deref for PathMap2d {
 impl PathMap for PathMap2d {
     type ExitIterator = IntoIter<IVec2, 8>;
     fn exits(&self, p: impl GridPoint) -> Self::ExitIterator {
+        let origin = p.xy();
         let mut points = ArrayVec::new();
-        for adj in p.adj_8() {
-            if !self.in_bounds(adj) {
+
+        if self.connectivity == Connectivity::Orthogonal {
+            for adj in origin.adj_4() {
+                if !self.blocks_movement(adj) {
+                    points.push(adj);
+                }
+            }
+            return points.into_iter();
+        }
+
+        for adj in origin.adj_8() {
+            if self.blocks_movement(adj) {
                 continue;
             }
 
-            if !self[adj] {
-                points.push(adj);
+            if self.connectivity == Connectivity::DiagonalNoCornerCut
+                && !corner_cells_clear(origin, adj, |c| !self.blocks_movement(c))
+            {
+                continue;
             }
+
+            points.push(adj);
         }
         points.into_iter()
     }
@@ -97,6 +151,7 @@ pub enum GridCell {
 
 pub struct PathMap2DWeighted {
     grid: Grid<GridCell>,
+    connectivity: Connectivity,
 }
 
 impl PathMap2DWeighted {
@@ -105,9 +160,16 @@ impl PathMap2DWeighted {
     pub fn new(size: impl Size2d) -> Self {
         Self {
             grid: Grid::default(size),
+            connectivity: Connectivity::default(),
         }
     }
 
+    /// Set the [Connectivity] used to determine valid movement exits.
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
     pub fn is_obstacle(&self, p: impl GridPoint) -> GridCell {
         self[p]
     }
@@ -115,6 +177,11 @@ impl PathMap2DWeighted {
     pub fn set_obstacle(&mut self, p: impl GridPoint, v: GridCell) {
         self[p] = v;
     }
+
+    /// A cell blocks movement if it's out of bounds or marked [GridCell::Blocked].
+    fn blocks_movement(&self, p: IVec2) -> bool {
+        !self.in_bounds(p) || matches!(self[p], GridCell::Blocked)
+    }
 }
 
 impl std::ops::DerefMut for PathMap2DWeighted {
@@ -134,15 +201,30 @@ impl std::ops::Deref for PathMap2DWeighted {
 impl PathMap for PathMap2DWeighted {
     type ExitIterator = IntoIter<IVec2, 8>;
     fn exits(&self, p: impl GridPoint) -> Self::ExitIterator {
+        let origin = p.xy();
         let mut points = ArrayVec::new();
-        for adj in p.adj_8() {
-            if !self.in_bounds(adj) {
+
+        if self.connectivity == Connectivity::Orthogonal {
+            for adj in origin.adj_4() {
+                if !self.blocks_movement(adj) {
+                    points.push(adj);
+                }
+            }
+            return points.into_iter();
+        }
+
+        for adj in origin.adj_8() {
+            if self.blocks_movement(adj) {
                 continue;
             }
 
-            if let GridCell::Weighted(_) | GridCell::NonWeighted = self[adj] {
-                points.push(adj);
+            if self.connectivity == Connectivity::DiagonalNoCornerCut
+                && !corner_cells_clear(origin, adj, |c| !self.blocks_movement(c))
+            {
+                continue;
             }
+
+            points.push(adj);
         }
         points.into_iter()
     }
@@ -167,3 +249,236 @@ impl PathMap for PathMap2DWeighted {
         a.taxi_dist(b)
     }
 }
+
+/// A pathmap backed by a grid of elevations, where traversability depends on
+/// the height difference between neighboring cells rather than a blocked
+/// flag, e.g. terrain with cliffs and ridges.
+///
+/// `exits` only yields a neighbor when stepping up to it is within
+/// `max_ascent` and stepping down to it is within `max_descent`; leave
+/// `max_descent` at its default of `i32::MAX` to allow falling any distance.
+pub struct PathMap2DHeight {
+    grid: Grid<i32>,
+    connectivity: Connectivity,
+    max_ascent: i32,
+    max_descent: i32,
+    slope_penalty: i32,
+}
+
+impl PathMap2DHeight {
+    /// Create a new height map from `heights`, with no ascent/descent limit
+    /// and no slope penalty.
+    pub fn new(heights: Grid<i32>) -> Self {
+        Self {
+            grid: heights,
+            connectivity: Connectivity::default(),
+            max_ascent: i32::MAX,
+            max_descent: i32::MAX,
+            slope_penalty: 0,
+        }
+    }
+
+    /// Set the [Connectivity] used to determine valid movement exits.
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
+    /// Limit how much a single step may climb.
+    pub fn with_max_ascent(mut self, max_ascent: i32) -> Self {
+        self.max_ascent = max_ascent;
+        self
+    }
+
+    /// Limit how much a single step may drop. Defaults to `i32::MAX`, which
+    /// allows falling any distance.
+    pub fn with_max_descent(mut self, max_descent: i32) -> Self {
+        self.max_descent = max_descent;
+        self
+    }
+
+    /// Add `penalty` extra cost per unit of height difference crossed by a
+    /// step, on top of the base cost of 1.
+    pub fn with_slope_penalty(mut self, penalty: i32) -> Self {
+        self.slope_penalty = penalty;
+        self
+    }
+
+    pub fn height(&self, p: impl GridPoint) -> i32 {
+        self[p]
+    }
+
+    /// Whether a step from `from` to `to` respects the ascent/descent limits.
+    fn climbable(&self, from: IVec2, to: IVec2) -> bool {
+        match self[to] - self[from] {
+            delta if delta >= 0 => delta <= self.max_ascent,
+            delta => -delta <= self.max_descent,
+        }
+    }
+}
+
+impl std::ops::DerefMut for PathMap2DHeight {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.grid
+    }
+}
+
+impl std::ops::Deref for PathMap2DHeight {
+    type Target = Grid<i32>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.grid
+    }
+}
+
+impl PathMap for PathMap2DHeight {
+    type ExitIterator = IntoIter<IVec2, 8>;
+    fn exits(&self, p: impl GridPoint) -> Self::ExitIterator {
+        let origin = p.xy();
+        let mut points = ArrayVec::new();
+
+        // Unlike its neighbors, `climbable` indexes the querying cell
+        // itself, so an out-of-bounds `origin` must be rejected up front
+        // instead of panicking on the grid index.
+        if !self.in_bounds(origin) {
+            return points.into_iter();
+        }
+
+        let passable = |c: IVec2| self.in_bounds(c) && self.climbable(origin, c);
+
+        if self.connectivity == Connectivity::Orthogonal {
+            for adj in origin.adj_4() {
+                if passable(adj) {
+                    points.push(adj);
+                }
+            }
+            return points.into_iter();
+        }
+
+        for adj in origin.adj_8() {
+            if !passable(adj) {
+                continue;
+            }
+
+            if self.connectivity == Connectivity::DiagonalNoCornerCut
+                && !corner_cells_clear(origin, adj, passable)
+            {
+                continue;
+            }
+
+            points.push(adj);
+        }
+        points.into_iter()
+    }
+
+    fn cost(&self, a: impl GridPoint, b: impl GridPoint) -> i32 {
+        if self.slope_penalty == 0 {
+            return 1;
+        }
+        let delta = (self[b] - self[a]).abs();
+        1 + delta * self.slope_penalty
+    }
+
+    fn distance(&self, a: impl GridPoint, b: impl GridPoint) -> usize {
+        a.taxi_dist(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn height_map_exits_out_of_bounds_origin_yields_none_instead_of_panicking() {
+        let heights: Grid<i32> = Grid::default([4, 4]);
+        let map = PathMap2DHeight::new(heights);
+
+        let exits: Vec<_> = map.exits(IVec2::new(-1, -1)).collect();
+
+        assert!(exits.is_empty());
+    }
+
+    #[test]
+    fn height_map_blocks_steps_above_max_ascent() {
+        let mut heights: Grid<i32> = Grid::default([3, 1]);
+        heights[[1, 0]] = 5;
+        let map = PathMap2DHeight::new(heights).with_max_ascent(1);
+
+        let exits: Vec<_> = map.exits(IVec2::new(0, 0)).collect();
+
+        assert!(!exits.contains(&IVec2::new(1, 0)));
+    }
+
+    #[test]
+    fn orthogonal_connectivity_excludes_diagonal_neighbors_on_path_map2d() {
+        let map = PathMap2d::new([3, 3]).with_connectivity(Connectivity::Orthogonal);
+
+        let exits: Vec<_> = map.exits(IVec2::new(1, 1)).collect();
+
+        assert_eq!(exits.len(), 4);
+        for diagonal in [
+            IVec2::new(0, 0),
+            IVec2::new(2, 0),
+            IVec2::new(0, 2),
+            IVec2::new(2, 2),
+        ] {
+            assert!(!exits.contains(&diagonal));
+        }
+    }
+
+    #[test]
+    fn orthogonal_connectivity_excludes_diagonal_neighbors_on_path_map2d_weighted() {
+        let map = PathMap2DWeighted::new([3, 3]).with_connectivity(Connectivity::Orthogonal);
+
+        let exits: Vec<_> = map.exits(IVec2::new(1, 1)).collect();
+
+        assert_eq!(exits.len(), 4);
+        for diagonal in [
+            IVec2::new(0, 0),
+            IVec2::new(2, 0),
+            IVec2::new(0, 2),
+            IVec2::new(2, 2),
+        ] {
+            assert!(!exits.contains(&diagonal));
+        }
+    }
+
+    #[test]
+    fn diagonal_no_corner_cut_rejects_blocked_corner_on_path_map2d() {
+        let mut map = PathMap2d::new([3, 3]).with_connectivity(Connectivity::DiagonalNoCornerCut);
+        map.set_obstacle([1, 0], true);
+
+        let exits: Vec<_> = map.exits(IVec2::new(0, 0)).collect();
+
+        assert!(!exits.contains(&IVec2::new(1, 1)));
+    }
+
+    #[test]
+    fn diagonal_no_corner_cut_allows_clear_diagonal_on_path_map2d() {
+        let map = PathMap2d::new([3, 3]).with_connectivity(Connectivity::DiagonalNoCornerCut);
+
+        let exits: Vec<_> = map.exits(IVec2::new(0, 0)).collect();
+
+        assert!(exits.contains(&IVec2::new(1, 1)));
+    }
+
+    #[test]
+    fn diagonal_no_corner_cut_rejects_blocked_corner_on_path_map2d_weighted() {
+        let mut map =
+            PathMap2DWeighted::new([3, 3]).with_connectivity(Connectivity::DiagonalNoCornerCut);
+        map.set_obstacle([1, 0], GridCell::Blocked);
+
+        let exits: Vec<_> = map.exits(IVec2::new(0, 0)).collect();
+
+        assert!(!exits.contains(&IVec2::new(1, 1)));
+    }
+
+    #[test]
+    fn diagonal_no_corner_cut_allows_clear_diagonal_on_path_map2d_weighted() {
+        let map = PathMap2DWeighted::new([3, 3]).with_connectivity(Connectivity::DiagonalNoCornerCut);
+
+        let exits: Vec<_> = map.exits(IVec2::new(0, 0)).collect();
+
+        assert!(exits.contains(&IVec2::new(1, 1)));
+    }
+}